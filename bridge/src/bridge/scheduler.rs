@@ -0,0 +1,118 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use futures::Future;
+use web3::Transport;
+use web3::types::{U256, H256, Address, Bytes};
+use app::App;
+use api;
+use config::Node;
+use error::Error;
+use ethcore_transaction::{Transaction, Action};
+use super::nonce::{NonceCheck, SendRawTransaction};
+
+/// something that can take a payload bound for a contract call and turn it into a
+/// signed, nonce-assigned, submitted transaction.
+///
+/// pulling this out from the relay streams keeps nonce allocation, gap detection and
+/// key rotation in one place instead of inside each stream that sends transactions.
+/// the withdraw relay submits through it; the deposit relay does not.
+pub trait Scheduler<T: Transport> {
+	/// resolves once the transaction has been accepted by the node, yielding the
+	/// nonce and hash it was submitted under so the caller can track it to completion
+	type Submission: Future<Item = (U256, H256), Error = Error>;
+
+	/// schedule `payload` to be sent to `to` with the given `gas`/`gas_price`. returns
+	/// the account the submission was (or will be) signed with alongside the submission
+	/// itself, chosen synchronously at schedule time, so a caller that needs to later
+	/// replace the transaction (same nonce, bumped gas price) knows which key to re-sign
+	/// with without waiting on `Submission` to resolve first
+	fn schedule(&self, to: Address, gas: U256, gas_price: U256, payload: Bytes) -> (Address, Self::Submission);
+}
+
+/// default `Scheduler`: owns nonce allocation for one or more accounts on a single
+/// chain, serializing submissions per account and filling nonce gaps the same way
+/// `NonceCheck` already did for the withdraw relay. when configured with more than
+/// one account it round-robins between them so a burst of withdraws isn't bottlenecked
+/// on a single account's strictly-increasing nonce - today every caller in this tree
+/// only ever configures one account, so that rotation is exercised by the unit tests
+/// below but not yet reachable in production; wiring up multiple configured accounts
+/// is follow-up work.
+pub struct AccountScheduler<T: Transport> {
+	app: Arc<App<T>>,
+	transport: T,
+	chain_id: u64,
+	node: Node,
+	accounts: Vec<Address>,
+	next_account: AtomicUsize,
+}
+
+impl<T: Transport> AccountScheduler<T> {
+	pub fn new(app: Arc<App<T>>, transport: T, node: Node, chain_id: u64, accounts: Vec<Address>) -> Self {
+		assert!(!accounts.is_empty(), "AccountScheduler needs at least one signing account");
+		AccountScheduler {
+			app,
+			transport,
+			chain_id,
+			node,
+			accounts,
+			next_account: AtomicUsize::new(0),
+		}
+	}
+
+	/// picks the account the current call to `schedule` signs with, advancing the
+	/// round-robin so the following call gets the next account
+	fn next_account(&self) -> Address {
+		let index = round_robin_index(&self.next_account, self.accounts.len());
+		self.accounts[index]
+	}
+}
+
+/// advances `counter` and wraps it into `[0, len)`, round-robin style
+fn round_robin_index(counter: &AtomicUsize, len: usize) -> usize {
+	counter.fetch_add(1, Ordering::SeqCst) % len
+}
+
+impl<T: Transport + Clone> Scheduler<T> for AccountScheduler<T> {
+	type Submission = NonceCheck<T, SendRawTransaction<T>>;
+
+	fn schedule(&self, to: Address, gas: U256, gas_price: U256, payload: Bytes) -> (Address, Self::Submission) {
+		let account = self.next_account();
+		let tx = Transaction {
+			gas,
+			gas_price,
+			value: U256::zero(),
+			data: payload.0,
+			nonce: U256::zero(),
+			action: Action::Call(to),
+		};
+		let submission = api::send_transaction_with_nonce(
+			self.transport.clone(),
+			self.app.clone(),
+			self.node.clone(),
+			account,
+			tx,
+			self.chain_id,
+			SendRawTransaction(self.transport.clone()));
+		(account, submission)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::AtomicUsize;
+	use super::round_robin_index;
+
+	#[test]
+	fn test_round_robin_index_cycles() {
+		let counter = AtomicUsize::new(0);
+		let indices: Vec<usize> = (0..5).map(|_| round_robin_index(&counter, 3)).collect();
+		assert_eq!(indices, vec![0, 1, 2, 0, 1]);
+	}
+
+	#[test]
+	fn test_round_robin_index_single_account() {
+		let counter = AtomicUsize::new(0);
+		let indices: Vec<usize> = (0..3).map(|_| round_robin_index(&counter, 1)).collect();
+		assert_eq!(indices, vec![0, 0, 0]);
+	}
+}
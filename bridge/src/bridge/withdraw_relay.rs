@@ -1,9 +1,9 @@
 use std::sync::{Arc, RwLock};
-use futures::{self, Future, Stream, stream::{Collect, FuturesUnordered, futures_unordered}, Poll};
+use futures::{self, Future, Stream, Poll};
 use futures::future::{JoinAll, join_all, Join};
 use tokio_timer::Timeout;
 use web3::Transport;
-use web3::types::{U256, Address, FilterBuilder, Log, Bytes};
+use web3::types::{U256, H256, Address, FilterBuilder, Log, Bytes};
 use ethabi::{RawLog, self};
 use app::App;
 use api::{self, LogStream, ApiCall};
@@ -14,7 +14,7 @@ use error::{self, Error, ErrorKind};
 use message_to_mainnet::MessageToMainnet;
 use signature::Signature;
 use ethcore_transaction::{Transaction, Action};
-use super::nonce::{NonceCheck, SendRawTransaction};
+use super::scheduler::{Scheduler, AccountScheduler};
 use super::BridgeChecked;
 use itertools::Itertools;
 
@@ -24,12 +24,31 @@ fn collected_signatures_filter<I: IntoIterator<Item = Address>>(foreign: &foreig
 	web3_filter(filter, addresses)
 }
 
+/// number of foreign blocks to look back, from the block a `CollectedSignatures` batch
+/// was seen at, when searching for the `Withdraw` events that originated it. bounds the
+/// `eth_getLogs` range so it doesn't scan the entire contract history (and doesn't get
+/// rejected outright by RPC providers that cap the range of a single query) while still
+/// comfortably covering the gap between a user's withdraw and the authorities finishing
+/// signature collection for it.
+const WITHDRAW_REQUEST_LOOKBACK_BLOCKS: u64 = 100_000;
+
+/// returns a filter for the `ForeignBridge.Withdraw` events that originate the messages
+/// a `CollectedSignatures` event later asks this authority to relay to `HomeBridge`,
+/// bounded to the range a withdraw request for `up_to_block` could plausibly appear in
+fn withdraw_request_filter<I: IntoIterator<Item = Address>>(foreign: &foreign::ForeignBridge, addresses: I, up_to_block: u64) -> FilterBuilder {
+	let filter = foreign.events().withdraw().create_filter()
+		.from_block(up_to_block.saturating_sub(WITHDRAW_REQUEST_LOOKBACK_BLOCKS).into())
+		.to_block(up_to_block.into());
+	web3_filter(filter, addresses)
+}
+
 /// payloads for calls to `ForeignBridge.signature` and `ForeignBridge.message`
 /// to retrieve the signatures (v, r, s) and messages
 /// which the withdraw relay process should later relay to `HomeBridge`
 /// by calling `HomeBridge.withdraw(v, r, s, message)`
 #[derive(Debug, PartialEq)]
 struct RelayAssignment {
+	message_hash: H256,
 	signature_payloads: Vec<Bytes>,
 	message_payload: Bytes,
 }
@@ -57,61 +76,244 @@ fn signatures_payload(foreign: &foreign::ForeignBridge, my_address: Address, log
 	let message_payload = foreign.functions().message().input(collected_signatures.message_hash).into();
 
 	Ok(Some(RelayAssignment {
+		message_hash: collected_signatures.message_hash,
 		signature_payloads,
 		message_payload,
 	}))
 }
 
+/// checks that a decoded `MessageToMainnet` is backed by a real `ForeignBridge.Withdraw`
+/// event, so a `CollectedSignatures` log can't be forged or mutated in flight into
+/// relaying funds to a recipient/amount nobody actually burned on the foreign side.
+///
+/// matches on `message.transaction_hash` first: recipient/value alone don't uniquely
+/// identify a withdraw request, so two independent withdrawals of the same amount by
+/// the same recipient would otherwise both validate against either one's `Withdraw` log.
+/// the tx hash is what the message actually commits to, so it's checked before falling
+/// back to recipient/value as a sanity check against the matched log's own payload.
+fn withdraw_request_exists(foreign: &foreign::ForeignBridge, message: &MessageToMainnet, logs: &[Log]) -> error::Result<bool> {
+	for log in logs {
+		if log.transaction_hash != Some(message.transaction_hash) {
+			continue;
+		}
+		let raw_log = RawLog {
+			topics: log.topics.iter().cloned().map(|t| t.0.into()).collect(),
+			data: log.data.0.clone(),
+		};
+		let withdraw = foreign.events().withdraw().parse_log(raw_log)?;
+		if withdraw.recipient == message.recipient.0.into() && withdraw.value == message.value {
+			return Ok(true);
+		}
+	}
+	Ok(false)
+}
+
+/// an in-flight `HomeBridge.withdraw` that has been submitted to the home chain but not
+/// yet observed as mined. this is the relay's "Eventuality": the claim that `tx_hash`
+/// will, eventually, cause `HomeBridge.withdraws(message_hash)` to become true.
+///
+/// kept in `Database` (keyed by `message_hash`, the same id `CollectedSignatures` uses)
+/// so a restart reloads outstanding withdraws and re-checks them instead of re-sending
+/// blindly, and so `checked_withdraw_relay` is only advanced once every withdraw
+/// assigned to a block has actually confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eventuality {
+	pub message_hash: H256,
+	pub payload: Bytes,
+	/// account the submission was signed with, so a resubmission re-signs with the same
+	/// key instead of an arbitrary one when the scheduler has more than one to choose from
+	pub account: Address,
+	pub tx_hash: H256,
+	pub nonce: U256,
+	pub gas_price: U256,
+	pub submitted_at_block: u64,
+	/// the foreign-chain log cursor (`RelayWithdraws`' `block`) this withdraw was
+	/// relayed for. `checked_withdraw_relay` is only allowed to advance past this once
+	/// the eventuality is confirmed - see `finalized_relay_cursor`'s use in `Wait`.
+	pub relay_block: u64,
+}
+
+/// number of home blocks an `Eventuality` is given to confirm before it is resubmitted
+/// with the same nonce and a bumped gas price
+const EVENTUALITY_RESUBMIT_AFTER_BLOCKS: u64 = 20;
+
+/// whether an unconfirmed `Eventuality` has been waiting long enough to be resubmitted.
+/// `None` (current home height not yet known) is never overdue, since there's nothing
+/// sensible to compare `submitted_at_block` against yet
+fn eventuality_overdue(home_block: Option<u64>, submitted_at_block: u64) -> bool {
+	home_block
+		.map(|home_block| home_block.saturating_sub(submitted_at_block) >= EVENTUALITY_RESUBMIT_AFTER_BLOCKS)
+		.unwrap_or(false)
+}
+
 /// state of the withdraw relay state machine
-pub enum WithdrawRelayState<T: Transport> {
+pub enum WithdrawRelayState<T: Transport, S: Scheduler<T>> {
 	Wait,
 	FetchMessagesSignatures {
 		future: Join<
 			JoinAll<Vec<Timeout<ApiCall<Bytes, T::Out>>>>,
 			JoinAll<Vec<JoinAll<Vec<Timeout<ApiCall<Bytes, T::Out>>>>>>
 		>,
+		message_hashes: Vec<H256>,
+		block: u64,
+	},
+	VerifyWithdrawRequests {
+		future: Timeout<ApiCall<Vec<Log>, T::Out>>,
+		message_hashes: Vec<H256>,
+		messages: Vec<Bytes>,
+		signatures: Vec<Vec<Signature>>,
 		block: u64,
 	},
 	RelayWithdraws {
-		future: Collect<FuturesUnordered<NonceCheck<T, SendRawTransaction<T>>>>,
+		future: JoinAll<Vec<S::Submission>>,
+		message_hashes: Vec<H256>,
+		payloads: Vec<Bytes>,
+		accounts: Vec<Address>,
+		gas_prices: Vec<U256>,
 		block: u64,
 	},
 	Yield(Option<u64>),
 }
 
-pub fn create_withdraw_relay<T: Transport + Clone>(app: Arc<App<T>>, init: &Database, home_balance: Arc<RwLock<Option<U256>>>, home_chain_id: u64, home_gas_price: Arc<RwLock<u64>>) -> WithdrawRelay<T> {
+/// a foreign chain finality checkpoint (e.g. the highest block covered by a GRANDPA
+/// justification or an analogous proof against the authority set), kept up to date by
+/// a producer *outside this module*. a `CollectedSignatures` log is only acted on once
+/// its block is at or below the checkpoint, instead of merely `required_confirmations`
+/// blocks old, so it can't be relayed against a block that later reorgs out on chains
+/// with deterministic finality.
+///
+/// this module only ever reads the checkpoint; it does not poll any authority/validator
+/// set itself. wiring up an actual producer (querying the foreign chain's validator set
+/// and writing the resulting checkpoint here) is follow-up work, not part of this change
+/// — pass `None` until one exists, since a `Some` with nothing ever updating it would
+/// wedge the relay at `info!("waiting for an initial foreign finality checkpoint...")`
+/// forever.
+///
+/// `None` disables finality gating entirely, falling back to the old confirmation-count
+/// behaviour (`required_confirmations`) unconditionally, same as before this existed.
+pub type FinalityCheckpoint = Arc<RwLock<Option<u64>>>;
+
+/// the furthest block a cursor may safely advance to, given the blocks of everything
+/// still outstanding (`still_pending`) and of everything that just became resolved
+/// (`ready`). it's only safe to move the cursor past a block once everything up to and
+/// including it has resolved, so the cursor can only advance as far as the oldest thing
+/// still outstanding — or, if nothing is outstanding, as far as the newest thing that
+/// just resolved.
+///
+/// used both to gate `checked_withdraw_relay` on foreign-chain finality (`still_pending`
+/// = blocks of buffered logs awaiting finality, `ready` = batches that just became final) and,
+/// in `Wait`'s confirmation branch, to gate it on home-chain confirmation (`still_pending`
+/// = unconfirmed `Eventuality::relay_block`s, `ready` = the latest block ever relayed).
+fn finalized_relay_cursor(still_pending: &[u64], ready: &[u64]) -> u64 {
+	still_pending.iter().cloned().min()
+		.map(|earliest_pending| earliest_pending.saturating_sub(1))
+		.unwrap_or_else(|| ready.iter().cloned().max().expect("ready is non-empty whenever still_pending is empty"))
+}
+
+pub fn create_withdraw_relay<T: Transport + Clone>(app: Arc<App<T>>, init: &Database, home_balance: Arc<RwLock<Option<U256>>>, home_chain_id: u64, home_gas_price: Arc<RwLock<u64>>, home_block: Arc<RwLock<Option<u64>>>, foreign_finality: Option<FinalityCheckpoint>) -> WithdrawRelay<T, AccountScheduler<T>> {
 	let logs_init = api::LogStreamInit {
 		after: init.checked_withdraw_relay,
 		request_timeout: app.config.foreign.request_timeout,
 		poll_interval: app.config.foreign.poll_interval,
-		confirmations: app.config.foreign.required_confirmations,
+		// when a finality checkpoint is configured, `Wait` gates on it directly instead
+		// (see `FinalityCheckpoint`); falling back to `required_confirmations` on top of
+		// that would just add latency for no extra safety, so it's only applied here when
+		// there's no finality source to gate on instead
+		confirmations: if foreign_finality.is_some() { 0 } else { app.config.foreign.required_confirmations },
 		filter: collected_signatures_filter(&app.foreign_bridge, vec![init.foreign_contract_address]),
 	};
 
+	// `config::Node` only ever carries a single configured `account`, so this is always
+	// a one-element vec: `AccountScheduler`'s round-robin is exercised (and tested) at
+	// the unit level, but with a single account configured here it never actually
+	// rotates keys in practice. raising parallel throughput via multiple signing
+	// accounts needs config plumbing for a list of home accounts, which doesn't exist
+	// in this tree yet - that's follow-up work, not delivered by this change
+	let scheduler = AccountScheduler::new(
+		app.clone(),
+		app.connections.home.clone(),
+		app.config.home.clone(),
+		home_chain_id,
+		vec![app.config.home.account]);
+
 	WithdrawRelay {
 		logs: api::log_stream(app.connections.foreign.clone(), app.timer.clone(), logs_init),
 		home_contract: init.home_contract_address,
 		foreign_contract: init.foreign_contract_address,
 		state: WithdrawRelayState::Wait,
+		scheduler,
+		pending: init.pending_withdraw_confirmations.clone(),
+		latest_relayed_block: Some(init.checked_withdraw_relay),
+		last_yielded_block: Some(init.checked_withdraw_relay),
+		confirming: None,
+		pending_logs: Vec::new(),
+		foreign_finality,
 		app,
 		home_balance,
 		home_chain_id,
 		home_gas_price,
+		home_block,
 	}
 }
 
-pub struct WithdrawRelay<T: Transport> {
+pub struct WithdrawRelay<T: Transport, S: Scheduler<T> = AccountScheduler<T>> {
 	app: Arc<App<T>>,
 	logs: LogStream<T>,
-	state: WithdrawRelayState<T>,
+	state: WithdrawRelayState<T, S>,
 	foreign_contract: Address,
 	home_contract: Address,
 	home_balance: Arc<RwLock<Option<U256>>>,
 	home_chain_id: u64,
 	home_gas_price: Arc<RwLock<u64>>,
+	/// current home chain block height, kept up to date by a background task the same
+	/// way `home_balance` and `home_gas_price` are. used to decide whether an `Eventuality`
+	/// is overdue for resubmission, so that decision is made against the chain the
+	/// transaction actually lives on rather than the foreign log stream's cursor.
+	home_block: Arc<RwLock<Option<u64>>>,
+	/// withdraws submitted to home but not yet confirmed mined. reloaded from
+	/// `Database::pending_withdraw_confirmations` on startup so a crash between
+	/// submitting a transaction and observing it mined doesn't cause it to be forgotten.
+	/// call `pending_withdraw_confirmations` after each streamed item to read this back
+	/// out and persist it, so that reload is actually kept up to date.
+	pending: Vec<Eventuality>,
+	/// the furthest block any `Eventuality` has ever been relayed for - i.e. the cursor
+	/// `checked_withdraw_relay` would advance to if `pending` became empty right now.
+	/// updated in `RelayWithdraws`, read back in `Wait`'s confirmation branch via
+	/// `finalized_relay_cursor`.
+	latest_relayed_block: Option<u64>,
+	/// the last cursor value actually streamed out as a `BridgeChecked::WithdrawRelay`,
+	/// so re-running the same (unconfirmed) computation on every confirmation poll
+	/// doesn't re-yield a value the caller already persisted.
+	last_yielded_block: Option<u64>,
+	/// in-flight `HomeBridge.withdraws(message_hash)` checks for `pending`, polled
+	/// opportunistically from `Wait` alongside fetching new `CollectedSignatures` logs
+	/// instead of gating the whole relay on them: a withdraw stuck waiting on
+	/// confirmation must never stop new withdraws from being relayed concurrently.
+	confirming: Option<(Vec<Eventuality>, JoinAll<Vec<Timeout<ApiCall<Bytes, T::Out>>>>)>,
+	/// nonce-aware submission of `HomeBridge.withdraw` calls, so this stream does no
+	/// nonce handling of its own. the deposit relay does not submit through it
+	scheduler: S,
+	/// `CollectedSignatures` logs already returned by the log stream (which applies no
+	/// confirmation count when `foreign_finality` is set, the only case this buffer is
+	/// used) but not yet covered by a finalized checkpoint. paired with
+	/// the log stream's `to` block so the database cursor can still be advanced once a
+	/// buffered log's block is finalized. see `FinalityCheckpoint`.
+	pending_logs: Vec<(Log, u64)>,
+	/// see `FinalityCheckpoint`: read-only from here, populated by a producer this
+	/// module does not implement
+	foreign_finality: Option<FinalityCheckpoint>,
 }
 
-impl<T: Transport> Stream for WithdrawRelay<T> {
+impl<T: Transport, S: Scheduler<T>> WithdrawRelay<T, S> {
+	/// withdraws submitted to home but not yet confirmed mined, for a caller to persist
+	/// into `Database::pending_withdraw_confirmations` (e.g. after every streamed item),
+	/// the same way `BridgeChecked::WithdrawRelay` is used to persist `checked_withdraw_relay`
+	pub fn pending_withdraw_confirmations(&self) -> &[Eventuality] {
+		&self.pending
+	}
+}
+
+impl<T: Transport, S: Scheduler<T>> Stream for WithdrawRelay<T, S> {
 	type Item = BridgeChecked;
 	type Error = Error;
 
@@ -133,9 +335,134 @@ impl<T: Transport> Stream for WithdrawRelay<T> {
 		loop {
 			let next_state = match self.state {
 				WithdrawRelayState::Wait => {
+					// progress confirmation checking for already-submitted withdraws alongside
+					// fetching new ones, rather than gating the latter on the former: a single
+					// stuck home tx must never stop new `CollectedSignatures` events from being
+					// relayed.
+					if self.confirming.is_none() && !self.pending.is_empty() {
+						let checking = self.pending.clone();
+						let confirmation_calls = checking.iter()
+							.map(|eventuality| {
+								let payload: Bytes = app.home_bridge.functions().withdraws().input(eventuality.message_hash).into();
+								timer.timeout(
+									api::call(t, contract, payload),
+									foreign_request_timeout)
+							})
+							.collect::<Vec<_>>();
+						self.confirming = Some((checking, join_all(confirmation_calls)));
+					}
+
+					if let Some((checking, mut confirming)) = self.confirming.take() {
+						match confirming.poll().map_err(|e| ErrorKind::ContextualizedError(Box::new(e), "checking withdraw confirmations on home"))? {
+							futures::Async::NotReady => self.confirming = Some((checking, confirming)),
+							futures::Async::Ready(confirmations) => {
+								let home_block = *self.home_block.read().unwrap();
+								let mut still_pending = Vec::new();
+								for (eventuality, confirmation) in checking.into_iter().zip(confirmations.into_iter()) {
+									let confirmed = app.home_bridge.functions().withdraws().output(confirmation.0.as_slice())
+										.map_err(error::Error::from)?;
+									if confirmed {
+										info!("withdraw {:?} confirmed on home in tx {:?}", eventuality.message_hash, eventuality.tx_hash);
+										continue;
+									}
+
+									if eventuality_overdue(home_block, eventuality.submitted_at_block) {
+										warn!("withdraw {:?} unmined after {} blocks, resubmitting tx {:?} with nonce {} at a higher gas price",
+											eventuality.message_hash, EVENTUALITY_RESUBMIT_AFTER_BLOCKS, eventuality.tx_hash, eventuality.nonce);
+										let bumped_gas_price = eventuality.gas_price.saturating_mul(U256::from(2));
+										let tx = Transaction {
+											gas,
+											gas_price: bumped_gas_price,
+											value: U256::zero(),
+											data: eventuality.payload.0.clone(),
+											nonce: eventuality.nonce,
+											action: Action::Call(contract),
+										};
+										// re-sign with the same account the original submission used (not
+										// necessarily the scheduler's current round-robin pick), so the
+										// replacement lands under the nonce it's meant to replace instead
+										// of colliding with whatever that other account's nonce is doing
+										let signed = api::sign_transaction(app.clone(), home.clone(), eventuality.account, tx, chain_id)?;
+										let _ = api::send_raw_transaction(t.clone(), signed);
+										still_pending.push(Eventuality {
+											gas_price: bumped_gas_price,
+											..eventuality
+										});
+									} else {
+										still_pending.push(eventuality);
+									}
+								}
+								self.pending = still_pending;
+
+								// only advance the cursor once every eventuality relayed for a block
+								// has confirmed - `relay_block` of whatever's still outstanding caps
+								// it, same way foreign-finality buffering caps it in the log-fetch
+								// branch below
+								if let Some(latest) = self.latest_relayed_block {
+									let still_pending_blocks = self.pending.iter().map(|e| e.relay_block).collect::<Vec<_>>();
+									let cursor = finalized_relay_cursor(&still_pending_blocks, &[latest]);
+									let already_yielded = self.last_yielded_block.map(|yielded| cursor <= yielded).unwrap_or(false);
+									if !already_yielded {
+										self.last_yielded_block = Some(cursor);
+										self.state = WithdrawRelayState::Yield(Some(cursor));
+										continue;
+									}
+								}
+							},
+						}
+					}
+
+					// nothing outstanding at all (e.g. the last batches had no withdraws
+					// assigned to this authority): the cursor moves straight to the latest
+					// relayed block, since there is no confirmation left to wait on
+					if self.pending.is_empty() {
+						if let Some(latest) = self.latest_relayed_block {
+							if self.last_yielded_block.map(|yielded| latest > yielded).unwrap_or(true) {
+								self.last_yielded_block = Some(latest);
+								self.state = WithdrawRelayState::Yield(Some(latest));
+								continue;
+							}
+						}
+					}
+
 					let item = try_stream!(self.logs.poll().map_err(|e| ErrorKind::ContextualizedError(Box::new(e), "polling foreign for collected signatures")));
 					info!("got {} new signed withdraws to relay", item.logs.len());
-					let assignments = item.logs
+
+					let (logs, block) = match self.foreign_finality {
+						None => (item.logs, item.to),
+						Some(ref checkpoint) => {
+							self.pending_logs.extend(item.logs.into_iter().map(|log| (log, item.to)));
+
+							let finalized = match *checkpoint.read().unwrap() {
+								Some(finalized) => finalized,
+								None => {
+									info!("waiting for an initial foreign finality checkpoint before relaying");
+									return Ok(futures::Async::NotReady);
+								},
+							};
+
+							let (ready, still_pending): (Vec<_>, Vec<_>) = self.pending_logs.drain(..)
+								.partition(|&(ref log, _)| log.block_number.map(|n| n.low_u64() <= finalized).unwrap_or(false));
+							self.pending_logs = still_pending;
+
+							if ready.is_empty() {
+								info!("{} withdraws buffered awaiting foreign chain finality", self.pending_logs.len());
+								return Ok(futures::Async::NotReady);
+							}
+
+							// cap by the block a buffered log was actually emitted at, not its
+							// batch's `to`: a batch can be split, with part of it still buffered
+							let still_pending_blocks = self.pending_logs.iter()
+								.map(|&(ref log, to)| log.block_number.map(|n| n.low_u64()).unwrap_or(to))
+								.collect::<Vec<_>>();
+							let ready_to = ready.iter().map(|&(_, to)| to).collect::<Vec<_>>();
+							let block = finalized_relay_cursor(&still_pending_blocks, &ready_to);
+
+							(ready.into_iter().map(|(log, _)| log).collect(), block)
+						},
+					};
+
+					let assignments = logs
 						.into_iter()
 						.map(|log| signatures_payload(
 								foreign_bridge,
@@ -143,8 +470,9 @@ impl<T: Transport> Stream for WithdrawRelay<T> {
 								 log))
 						.collect::<error::Result<Vec<_>>>()?;
 
+					let assignments = assignments.into_iter().filter_map(|a| a).collect::<Vec<_>>();
+					let message_hashes = assignments.iter().map(|a| a.message_hash).collect::<Vec<_>>();
 					let (signatures, messages): (Vec<_>, Vec<_>) = assignments.into_iter()
-						.filter_map(|a| a)
 						.map(|assignment| (assignment.signature_payloads, assignment.message_payload))
 						.unzip();
 
@@ -172,10 +500,11 @@ impl<T: Transport> Stream for WithdrawRelay<T> {
 					info!("fetching messages and signatures");
 					WithdrawRelayState::FetchMessagesSignatures {
 						future: join_all(message_calls).join(join_all(signature_calls)),
-						block: item.to,
+						message_hashes,
+						block,
 					}
 				},
-				WithdrawRelayState::FetchMessagesSignatures { ref mut future, block } => {
+				WithdrawRelayState::FetchMessagesSignatures { ref mut future, ref message_hashes, block } => {
 					let home_balance = self.home_balance.read().unwrap();
 					if home_balance.is_none() {
 						warn!("home contract balance is unknown");
@@ -199,8 +528,6 @@ impl<T: Transport> Stream for WithdrawRelay<T> {
 						.collect::<ethabi::Result<Vec<_>>>()
 						.map_err(error::Error::from)?;
 
-					let len = messages.len();
-
 					let signatures = signatures_raw
 						.iter()
 						.map(|signatures|
@@ -219,36 +546,113 @@ impl<T: Transport> Stream for WithdrawRelay<T> {
 						)
 						.collect::<error::Result<Vec<_>>>()?;
 
-					let relays = messages.into_iter()
-						.zip(signatures.into_iter())
-						.map(|(message, signatures)| {
+					// one query, bounded to the `WITHDRAW_REQUEST_LOOKBACK_BLOCKS` window ending
+					// at this batch's block and shared across every
+					// pending message, instead of repeating an identical unbounded query per
+					// message: the set of `Withdraw` logs any of these messages could reference
+					// is the same regardless of which message_hash is being checked
+					let verify_filter = withdraw_request_filter(foreign_bridge, vec![foreign_contract], block).build();
+					let verify_call = timer.timeout(
+						api::logs(foreign, verify_filter),
+						foreign_request_timeout);
+
+					info!("verifying {} withdraw requests against foreign chain", messages.len());
+					WithdrawRelayState::VerifyWithdrawRequests {
+						future: verify_call,
+						message_hashes,
+						messages,
+						signatures,
+						block,
+					}
+				},
+				WithdrawRelayState::VerifyWithdrawRequests { ref mut future, ref message_hashes, ref messages, ref signatures, block } => {
+					let logs = try_ready!(future.poll().map_err(|e| ErrorKind::ContextualizedError(Box::new(e), "verifying withdraw requests on foreign")));
+					info!("verifying withdraw requests complete");
+
+					let verified = message_hashes.iter()
+						.zip(messages.iter())
+						.zip(signatures.iter())
+						.filter_map(|((message_hash, message), signatures)| {
+							let decoded = MessageToMainnet::from_bytes(message.0.as_slice());
+							match withdraw_request_exists(foreign_bridge, &decoded, &logs) {
+								Ok(true) => Some((*message_hash, message.clone(), signatures.clone())),
+								Ok(false) => {
+									warn!("dropping withdraw to {:?} for {} wei: no matching withdraw request found on foreign", decoded.recipient, decoded.value);
+									None
+								},
+								Err(e) => {
+									warn!("dropping withdraw to {:?}: failed to verify withdraw request: {}", decoded.recipient, e);
+									None
+								},
+							}
+						})
+						.collect_vec();
+
+					let mut message_hashes = Vec::with_capacity(verified.len());
+					let mut payloads = Vec::with_capacity(verified.len());
+					let mut accounts = Vec::with_capacity(verified.len());
+					let mut gas_prices = Vec::with_capacity(verified.len());
+					let relays = verified.into_iter()
+						.map(|(message_hash, message, signatures)| {
 							let payload: Bytes = app.home_bridge.functions().withdraw().input(
 								signatures.iter().map(|x| x.v),
 								signatures.iter().map(|x| x.r),
 								signatures.iter().map(|x| x.s),
 								message.clone().0).into();
 							let gas_price = MessageToMainnet::from_bytes(message.0.as_slice()).mainnet_gas_price;
-							let tx = Transaction {
-									gas,
-									gas_price,
-									value: U256::zero(),
-									data: payload.0,
-									nonce: U256::zero(),
-									action: Action::Call(contract),
-								};
-							    api::send_transaction_with_nonce(t.clone(), app.clone(), home.clone(), tx, chain_id, SendRawTransaction(t.clone()))
-							}).collect_vec();
-
-					info!("relaying {} withdraws", len);
+							let (account, submission) = self.scheduler.schedule(contract, gas, gas_price, payload.clone());
+							message_hashes.push(message_hash);
+							payloads.push(payload);
+							accounts.push(account);
+							gas_prices.push(gas_price);
+							submission
+						}).collect_vec();
+
+					info!("relaying {} withdraws", relays.len());
 					WithdrawRelayState::RelayWithdraws {
-						future: futures_unordered(relays).collect(),
+						future: join_all(relays),
+						message_hashes,
+						payloads,
+						accounts,
+						gas_prices,
 						block,
 					}
 				},
-				WithdrawRelayState::RelayWithdraws { ref mut future, block } => {
-					let _ = try_ready!(future.poll().map_err(|e| ErrorKind::ContextualizedError(Box::new(e), "sending withdrawal to home")));
-					info!("relaying withdraws complete");
-					WithdrawRelayState::Yield(Some(block))
+				WithdrawRelayState::RelayWithdraws { ref mut future, ref message_hashes, ref payloads, ref accounts, ref gas_prices, block } => {
+					let submitted = try_ready!(future.poll().map_err(|e| ErrorKind::ContextualizedError(Box::new(e), "sending withdrawal to home")));
+					info!("submitted {} withdraws to home, awaiting confirmation", submitted.len());
+
+					// if the current home height isn't known yet, record 0: the first
+					// confirmation check made once the height is known then treats the
+					// withdraw as overdue (if it is still unconfirmed) and resubmits it at a
+					// bumped gas price, rather than waiting `EVENTUALITY_RESUBMIT_AFTER_BLOCKS`
+					let submitted_at_block = self.home_block.read().unwrap().unwrap_or(0);
+					let newly_submitted = message_hashes.iter()
+						.zip(payloads.iter())
+						.zip(accounts.iter())
+						.zip(gas_prices.iter())
+						.zip(submitted.into_iter())
+						.map(|((((message_hash, payload), account), gas_price), (nonce, tx_hash))| {
+							Eventuality {
+								message_hash: *message_hash,
+								payload: payload.clone(),
+								account: *account,
+								tx_hash,
+								nonce,
+								gas_price: *gas_price,
+								submitted_at_block,
+								relay_block: block,
+							}
+						});
+					self.pending.extend(newly_submitted);
+					self.latest_relayed_block = Some(self.latest_relayed_block.map(|latest| latest.max(block)).unwrap_or(block));
+
+					// cursor does NOT advance here: it only moves from `Wait`, once every
+					// eventuality relayed for `block` (and anything still older) has
+					// actually confirmed on home, not merely been accepted
+					// by the RPC - see `Eventuality::relay_block`
+					info!("all withdraws up to block {} submitted, awaiting confirmation before advancing cursor", block);
+					WithdrawRelayState::Wait
 				},
 				WithdrawRelayState::Yield(ref mut block) => match block.take() {
 					None => {
@@ -325,4 +729,34 @@ mod tests {
 		let assignment = signatures_payload(&foreign, my_address, log).unwrap();
 		assert_eq!(None, assignment);
 	}
+
+	#[test]
+	fn test_finalized_relay_cursor_caps_at_oldest_pending() {
+		use super::finalized_relay_cursor;
+
+		assert_eq!(finalized_relay_cursor(&[50, 80], &[100]), 49);
+	}
+
+	#[test]
+	fn test_finalized_relay_cursor_uses_ready_max_when_nothing_pending() {
+		use super::finalized_relay_cursor;
+
+		assert_eq!(finalized_relay_cursor(&[], &[10, 20]), 20);
+	}
+
+	#[test]
+	fn test_finalized_relay_cursor_does_not_underflow_at_genesis() {
+		use super::finalized_relay_cursor;
+
+		assert_eq!(finalized_relay_cursor(&[0], &[0]), 0);
+	}
+
+	#[test]
+	fn test_eventuality_overdue() {
+		use super::eventuality_overdue;
+
+		assert!(!eventuality_overdue(None, 100), "unknown home height is never overdue");
+		assert!(!eventuality_overdue(Some(105), 100), "not enough blocks have passed yet");
+		assert!(eventuality_overdue(Some(120), 100), "20 blocks have passed, at the threshold");
+	}
 }